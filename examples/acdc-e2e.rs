@@ -8,6 +8,8 @@ use keri_acdc::{
     acdc,
     error::{Error, Result},
     keri::{self, KeriStore, KeySet},
+    recovery,
+    suite::CipherSuite,
 };
 
 struct Store {
@@ -350,18 +352,40 @@ struct Vault {
 }
 
 impl Vault {
-    pub fn new() -> Result<(Self, String)> {
+    pub fn new(suite: &CipherSuite) -> Result<(Self, String)> {
+        Self::incept(suite, None, None)
+    }
+
+    /// Rebuilds an identifier entirely from a passphrase, with no dependence on a surviving
+    /// `Store`. The establishment seed and its pre-rotated successor are both derived
+    /// deterministically, so re-running `recover` with the same passphrase and suite always
+    /// reproduces the same AID.
+    pub fn recover(suite: &CipherSuite, passphrase: &str) -> Result<(Self, String)> {
+        let root = recovery::derive_root_seed(passphrase)?;
+        let seed = recovery::derive_signing_seed(&root, 0)?;
+        let next_seed = recovery::derive_signing_seed(&root, 1)?;
+
+        Self::incept(suite, Some(&seed), Some(&next_seed))
+    }
+
+    fn incept(
+        suite: &CipherSuite,
+        seed: Option<&[u8]>,
+        next_seed: Option<&[u8]>,
+    ) -> Result<(Self, String)> {
+        suite.validate()?;
+
         let (aid, keys, icp) = keri::kmi::incept(
-            Some(cesride::matter::Codex::CRYSTALS_Dilithium3_Seed),
-            None,
-            None,
-            Some(cesride::matter::Codex::CRYSTALS_Dilithium3_Seed),
+            Some(suite.code),
+            seed,
             None,
+            Some(suite.next_code),
+            next_seed,
             None,
             None,
             Some(true),
-            Some(cesride::matter::Codex::Blake3_256),
-            None,
+            Some(suite.digest_code),
+            suite.hybrid_code,
         )?;
         let (registry, vcp) = acdc::tel::management::incept(&aid)?;
         let seal = dat!([{"i": &registry, "s": "0", "d": &registry}]);
@@ -627,11 +651,13 @@ fn main() {
     println!("primed schema cache");
     println!();
 
-    let (mut issuer_vault, _issuer_aid) = Vault::new().unwrap();
+    let suite = CipherSuite::default();
+
+    let (mut issuer_vault, _issuer_aid) = Vault::new(&suite).unwrap();
     println!("incepted `issuer` vault");
-    let (mut issuee_vault, issuee_aid) = Vault::new().unwrap();
+    let (mut issuee_vault, issuee_aid) = Vault::new(&suite).unwrap();
     println!("incepted `issuee` vault");
-    let (mut disclosee_vault, _disclosee_aid) = Vault::new().unwrap();
+    let (mut disclosee_vault, _disclosee_aid) = Vault::new(&suite).unwrap();
     println!("incepted `disclosee` vault");
     println!();
 
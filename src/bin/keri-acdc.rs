@@ -0,0 +1,255 @@
+//! Command-line front end for the `Vault` lifecycle.
+//!
+//! Before this existed, driving the crate meant editing `examples/acdc-e2e.rs` - there was no
+//! way to incept an identifier, issue a credential, or replay the partial-disclosure flow from
+//! the shell. Each subcommand below except `inspect` (which reads a `keri_acdc::store::Store`
+//! directly) is a thin wrapper around the matching `keri_acdc::vault` call, reading and
+//! writing CESR message streams on stdin/stdout so vaults can be piped
+//! between separate invocations (and separate processes) the way `main` currently does in a
+//! single one.
+
+use std::io::{self, Read, Write};
+
+use clap::{Parser, Subcommand};
+use keri_acdc::{
+    error::Result,
+    schema_resolver::{FilesystemResolver, HttpResolver, SchemaResolver},
+    store::Store,
+    suite::CipherSuite,
+    vault::Vault,
+};
+
+#[derive(Parser)]
+#[command(name = "keri-acdc", about = "Drive a keri-acdc Vault from the shell")]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+/// Flags shared by every subcommand that may need to resolve a schema SAID the local cache
+/// hasn't seen before.
+#[derive(clap::Args)]
+struct SchemaSourceArgs {
+    /// Resolve unknown schema SAIDs from `{dir}/{said}.json` on the local filesystem.
+    #[arg(long = "schema-dir")]
+    schema_dir: Option<String>,
+    /// Resolve unknown schema SAIDs over HTTP from `{origin}/{said}.json` (repeatable). Only the
+    /// first origin given is fetched from; all of them form the fetcher's allow-list, so a
+    /// resolved URL outside these origins is rejected. Ignored when `--schema-dir` is given.
+    #[arg(long = "schema-origin")]
+    schema_origins: Vec<String>,
+}
+
+fn configure_resolver(args: &SchemaSourceArgs) -> Result<Option<Box<dyn SchemaResolver>>> {
+    if let Some(dir) = &args.schema_dir {
+        return Ok(Some(Box::new(FilesystemResolver::new(dir))));
+    }
+
+    if !args.schema_origins.is_empty() {
+        return Ok(Some(Box::new(HttpResolver::new(args.schema_origins.clone())?)));
+    }
+
+    Ok(None)
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Incept (or recover) a vault and persist it at `--path`.
+    Incept {
+        /// Directory the vault's store is persisted under.
+        #[arg(long)]
+        path: String,
+        /// Recover deterministically from a passphrase instead of generating random keys.
+        #[arg(long)]
+        passphrase: Option<String>,
+        /// Number of rotations to replay after inception when recovering (ignored otherwise).
+        #[arg(long, default_value_t = 0)]
+        rotations: u32,
+    },
+    /// Issue an ACDC from an already-incepted vault.
+    Issue {
+        #[arg(long)]
+        path: String,
+        #[arg(long)]
+        prefix: String,
+        #[arg(long)]
+        registry: String,
+        /// Schema SAID the credential is issued against.
+        #[arg(long)]
+        schema: String,
+        /// JSON-encoded attributes block.
+        #[arg(long)]
+        data: String,
+        #[arg(long)]
+        recipient: Option<String>,
+        #[arg(long)]
+        private: bool,
+        /// JSON-encoded rules block.
+        #[arg(long)]
+        rules: Option<String>,
+        #[command(flatten)]
+        schema_source: SchemaSourceArgs,
+    },
+    /// Fetch an ACDC, optionally disclosing only a subset of its attributes.
+    Fetch {
+        #[arg(long)]
+        path: String,
+        #[arg(long)]
+        prefix: String,
+        #[arg(long)]
+        registry: String,
+        /// SAID of the ACDC to fetch.
+        #[arg(long)]
+        said: String,
+        /// Attribute names to disclose.
+        #[arg(long = "disclose")]
+        to_disclose: Vec<String>,
+        /// Include the issuer's KEL and the credential's TELs.
+        #[arg(long)]
+        full: bool,
+        #[command(flatten)]
+        schema_source: SchemaSourceArgs,
+    },
+    /// Ingest a CESR message stream from stdin (or `--file`) into a vault.
+    Ingest {
+        #[arg(long)]
+        path: String,
+        #[arg(long)]
+        prefix: String,
+        #[arg(long)]
+        registry: String,
+        /// Read the CESR stream from this file instead of stdin.
+        #[arg(long)]
+        file: Option<String>,
+        #[command(flatten)]
+        schema_source: SchemaSourceArgs,
+    },
+    /// Dump a prefix's KEL/TEL and establishment-event count.
+    Inspect {
+        #[arg(long)]
+        path: String,
+        /// Prefix to inspect.
+        #[arg(long)]
+        prefix: String,
+        /// Also dump the TEL for this registry/credential prefix.
+        #[arg(long)]
+        tel: Option<String>,
+    },
+}
+
+fn main() -> Result<()> {
+    let cli = Cli::parse();
+
+    match cli.command {
+        Command::Incept {
+            path,
+            passphrase,
+            rotations,
+        } => {
+            let suite = CipherSuite::default();
+            let (vault, aid) = match passphrase {
+                Some(passphrase) => Vault::recover(&path, &suite, &passphrase, rotations)?,
+                None => Vault::new(&path, &suite)?,
+            };
+            println!("{aid} {}", vault.registry());
+        }
+        Command::Issue {
+            path,
+            prefix,
+            registry,
+            schema,
+            data,
+            recipient,
+            private,
+            rules,
+            schema_source,
+        } => {
+            let mut vault = Vault::open(&path, &prefix, &registry)?;
+            if let Some(resolver) = configure_resolver(&schema_source)? {
+                vault.set_resolver(resolver);
+            }
+
+            let (said, messages) = vault.issue_acdc(
+                &schema,
+                &data,
+                recipient.as_deref(),
+                Some(private),
+                None,
+                rules.as_deref(),
+                None,
+            )?;
+            eprintln!("issued {said}");
+            io::stdout().write_all(messages.as_bytes())?;
+        }
+        Command::Fetch {
+            path,
+            prefix,
+            registry,
+            said,
+            to_disclose,
+            full,
+            schema_source,
+        } => {
+            let mut vault = Vault::open(&path, &prefix, &registry)?;
+            if let Some(resolver) = configure_resolver(&schema_source)? {
+                vault.set_resolver(resolver);
+            }
+
+            let fields: Vec<&str> = to_disclose.iter().map(String::as_str).collect();
+            let messages = vault.fetch_acdc(&said, &fields, full)?;
+            io::stdout().write_all(messages.as_bytes())?;
+        }
+        Command::Ingest {
+            path,
+            prefix,
+            registry,
+            file,
+            schema_source,
+        } => {
+            let mut vault = Vault::open(&path, &prefix, &registry)?;
+            if let Some(resolver) = configure_resolver(&schema_source)? {
+                vault.set_resolver(resolver);
+            }
+
+            let messages = read_stream(file)?;
+            vault.ingest_messages(&messages)?;
+        }
+        Command::Inspect { path, prefix, tel } => {
+            let store = Store::open(&path, &prefix)?;
+            print_kel_and_tel(&store, &prefix, tel.as_deref())?;
+        }
+    }
+
+    Ok(())
+}
+
+fn read_stream(file: Option<String>) -> Result<String> {
+    let mut buf = String::new();
+    match file {
+        Some(path) => {
+            buf = std::fs::read_to_string(path)?;
+        }
+        None => {
+            io::stdin().read_to_string(&mut buf)?;
+        }
+    }
+    Ok(buf)
+}
+
+fn print_kel_and_tel(store: &Store, prefix: &str, tel_prefix: Option<&str>) -> Result<()> {
+    use keri_acdc::keri::KeriStore;
+
+    println!("kel ({} establishment event(s)):", store.count_establishment_events(prefix)?);
+    for event in store.get_kel(prefix)? {
+        println!("{event}");
+    }
+
+    if let Some(tel_prefix) = tel_prefix {
+        println!("tel:");
+        for event in store.get_tel(tel_prefix)? {
+            println!("{event}");
+        }
+    }
+
+    Ok(())
+}
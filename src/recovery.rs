@@ -0,0 +1,115 @@
+//! Deterministic key derivation from a passphrase.
+//!
+//! KERI's pre-rotation model only requires that the *next* key's digest was committed to in
+//! an earlier event - it never requires the signing seed itself to be stored anywhere durable.
+//! That means a lost [`crate::store::Store`] doesn't have to mean a lost identifier, as long as
+//! every establishment key was derived from a passphrase instead of generated at random.
+//!
+//! A 32-byte root seed is stretched from the passphrase with Argon2id, using a fixed
+//! domain-separation salt so the same passphrase always yields the same root on any machine.
+//! Each establishment index `n` then derives its own signing seed via
+//! `HKDF-SHA256(root, info = "keri/signing" || n)`, so index `n`'s pre-rotated next key is the
+//! same seed that index `n + 1` will sign with - the invariant `Vault::recover` checks as it
+//! walks the KEL forward.
+
+use argon2::Argon2;
+use hkdf::Hkdf;
+use sha2::Sha256;
+
+use crate::{
+    error::{Error, Result},
+    keri::{self, KeySet},
+};
+
+
+/// Domain-separation salt for root seed derivation. Fixed (not random) so a passphrase alone
+/// is sufficient to reconstruct the root seed on any machine.
+const ROOT_SALT: &[u8] = b"keri-acdc/recovery/root/v1";
+
+/// Derives the 32-byte root seed a [`crate::keri::KeySet`] chain is built from.
+pub fn derive_root_seed(passphrase: &str) -> Result<[u8; 32]> {
+    let mut root = [0u8; 32];
+    Argon2::default()
+        .hash_password_into(passphrase.as_bytes(), ROOT_SALT, &mut root)
+        .map_err(|_| Error::Programmer)?;
+    Ok(root)
+}
+
+/// Derives the 32-byte seed for establishment index `n` from the root seed.
+///
+/// Index `n`'s pre-rotated next key is `derive_signing_seed(root, n + 1)`, which is why
+/// `Vault::recover` can walk the chain forward purely from `root` and the establishment
+/// index, without ever persisting a seed.
+pub fn derive_signing_seed(root: &[u8; 32], index: u32) -> Result<[u8; 32]> {
+    let hk = Hkdf::<Sha256>::new(None, root);
+    let mut info = b"keri/signing".to_vec();
+    info.extend_from_slice(&index.to_be_bytes());
+
+    let mut seed = [0u8; 32];
+    hk.expand(&info, &mut seed).map_err(|_| Error::Programmer)?;
+    Ok(seed)
+}
+
+impl KeySet {
+    /// Rebuilds the establishment `KeySet` for index `n` of a recoverable identifier.
+    ///
+    /// `root` is the output of [`derive_root_seed`]. The current signing seed is
+    /// `derive_signing_seed(root, n)`; the pre-rotated next-key seed is
+    /// `derive_signing_seed(root, n + 1)` - the same seed index `n + 1`'s own call to
+    /// `derive` will sign with, which is the invariant that makes recovery possible: the
+    /// digest committed to in event `n` always equals the digest of the key derived for
+    /// event `n + 1`.
+    pub fn derive(code: &str, next_code: &str, root: &[u8; 32], index: u32) -> Result<Self> {
+        let seed = derive_signing_seed(root, index)?;
+        let next_seed = derive_signing_seed(root, index + 1)?;
+
+        keri::kmi::keys_from_seeds(code, &seed, next_code, &next_seed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn same_passphrase_yields_the_same_root_seed() {
+        let a = derive_root_seed("correct horse battery staple").unwrap();
+        let b = derive_root_seed("correct horse battery staple").unwrap();
+        assert_eq!(a, b);
+    }
+
+    #[test]
+    fn different_indices_yield_different_seeds() {
+        let root = derive_root_seed("correct horse battery staple").unwrap();
+        assert_ne!(
+            derive_signing_seed(&root, 0).unwrap(),
+            derive_signing_seed(&root, 1).unwrap()
+        );
+    }
+
+    #[test]
+    fn the_pre_rotation_invariant_holds_through_an_actual_rotation() {
+        // the key `Vault::rotate` actually signs with at each establishment index must match
+        // what `KeySet::derive` reconstructs for that index from the passphrase alone - that
+        // agreement, checked against the committed keys in the Store (not against
+        // `derive_signing_seed` called twice), is what makes recovery possible at all.
+        use crate::{suite::CipherSuite, vault::Vault};
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("recoverable.sled");
+        let path = path.to_str().unwrap();
+
+        let suite = CipherSuite::default();
+        let passphrase = "correct horse battery staple";
+        let root = derive_root_seed(passphrase).unwrap();
+
+        let (vault, _aid) = Vault::recover(path, &suite, passphrase, 3).unwrap();
+
+        let expected = KeySet::derive(suite.code, suite.next_code, &root, 3).unwrap();
+        let committed = vault.current_keys().unwrap();
+        assert_eq!(
+            serde_json::to_string(&expected).unwrap(),
+            serde_json::to_string(&committed).unwrap()
+        );
+    }
+}
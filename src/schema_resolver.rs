@@ -0,0 +1,174 @@
+//! Pluggable schema resolution for the `Schemer` cache.
+//!
+//! `acdc::schemer::cache().prime(...)` only ever knows about schemas it was handed in-process,
+//! so `issue_acdc`/`expand_acdc`/`ingest_messages` fail with `Error::SchemaValidation` the
+//! moment a credential references a schema SAID the local cache has never seen. A
+//! [`SchemaResolver`] lets a `Vault` go fetch the schema document instead of giving up - from a
+//! directory of schema files, or from an HTTP origin the caller has explicitly marked as
+//! trusted.
+
+use std::path::PathBuf;
+
+use crate::{
+    acdc::schemer::Schemer,
+    error::{err, Error, Result},
+};
+
+/// Resolves a schema SAID to its document on a cache miss.
+pub trait SchemaResolver {
+    fn resolve(&self, said: &str) -> Result<Schemer>;
+}
+
+/// Resolves schemas from `{root}/{said}.json` on the local filesystem.
+pub struct FilesystemResolver {
+    root: PathBuf,
+}
+
+impl FilesystemResolver {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        FilesystemResolver { root: root.into() }
+    }
+}
+
+impl SchemaResolver for FilesystemResolver {
+    fn resolve(&self, said: &str) -> Result<Schemer> {
+        let path = self.root.join(format!("{said}.json"));
+        let document = std::fs::read(path)?;
+        verified_schemer(said, &document)
+    }
+}
+
+/// Resolves schemas over HTTP, refusing to fetch from any origin not on `allowed_origins`.
+///
+/// Schema documents are untrusted input until their self-addressing SAID is checked, so every
+/// fetch is verified against the requested `said` before it's handed to the caller - a server
+/// (or a path on the way to it) that returns the wrong document fails resolution rather than
+/// silently poisoning the cache.
+pub struct HttpResolver {
+    client: reqwest::Client,
+    allowed_origins: Vec<String>,
+    runtime: tokio::runtime::Runtime,
+}
+
+impl HttpResolver {
+    /// `allowed_origins` are scheme+host(+port) origins (e.g. `"https://schemas.example.com"`)
+    /// that `resolve` is permitted to fetch from, compared for exact equality (not prefix) so a
+    /// lookalike host can't sneak past the allow-list; a SAID resolved to a URL outside this
+    /// list is rejected before any network call is made.
+    pub fn new(allowed_origins: Vec<String>) -> Result<Self> {
+        Ok(HttpResolver {
+            client: reqwest::Client::new(),
+            allowed_origins,
+            runtime: tokio::runtime::Runtime::new()?,
+        })
+    }
+
+    /// Compares `url`'s scheme+host+port against each allow-list entry for exact equality -
+    /// not a string prefix, which a same-prefixed-but-different host (`https://trusted.example.com.attacker.net`)
+    /// or a concatenated suffix (`https://trusted.example.comevil.com`) would both satisfy.
+    fn origin_is_trusted(&self, url: &str) -> bool {
+        let Ok(url) = reqwest::Url::parse(url) else {
+            return false;
+        };
+
+        self.allowed_origins.iter().any(|origin| {
+            match reqwest::Url::parse(origin) {
+                Ok(origin) => url.origin() == origin.origin(),
+                Err(_) => false,
+            }
+        })
+    }
+
+    async fn fetch(&self, url: &str, said: &str) -> Result<Schemer> {
+        if !self.origin_is_trusted(url) {
+            return err!(Error::SchemaValidation(format!(
+                "origin not in allow-list: {url}"
+            )));
+        }
+
+        let document = self.client.get(url).send().await?.bytes().await?;
+        verified_schemer(said, &document)
+    }
+
+    /// Resolves `said` by fetching `url`, verifying the response's self-addressing SAID
+    /// matches `said` before it's returned. The result is not primed into the schema cache.
+    pub async fn resolve_url(&self, url: &str, said: &str) -> Result<Schemer> {
+        self.fetch(url, said).await
+    }
+}
+
+impl SchemaResolver for HttpResolver {
+    /// Synchronous entry point for callers that aren't already inside a tokio runtime; the
+    /// `said` is treated as a path appended to the first allowed origin.
+    fn resolve(&self, said: &str) -> Result<Schemer> {
+        let origin = self
+            .allowed_origins
+            .first()
+            .ok_or(Error::SchemaValidation("no allowed origins configured".to_string()))?;
+        let url = format!("{origin}/{said}.json");
+
+        self.runtime.block_on(self.fetch(&url, said))
+    }
+}
+
+fn verified_schemer(expected_said: &str, document: &[u8]) -> Result<Schemer> {
+    let schemer = Schemer::new(Some(document), None, None, None)?;
+    if schemer.said()? != expected_said {
+        return err!(Error::SchemaValidation(format!(
+            "resolved schema's SAID did not match the requested SAID: {expected_said}"
+        )));
+    }
+
+    Ok(schemer)
+}
+
+/// Looks `said` up in the schema cache, falling back to `resolver` on a miss and priming the
+/// cache with whatever it resolves so later lookups for the same SAID stay in-process.
+///
+/// `Vault::prime_schema` calls this ahead of `issue_acdc`/`expand_acdc`/`ingest_messages`
+/// whenever a resolver has been configured via `Vault::set_resolver`, so a schema miss is
+/// resolved before `Error::SchemaValidation` would otherwise surface from deeper in those calls
+/// - letting a `Vault` validate ACDCs whose schemas live off-box, whether it's issuing them or
+/// receiving them from someone else.
+pub fn resolve_with_fallback(said: &str, resolver: &dyn SchemaResolver) -> Result<Schemer> {
+    if let Some(schemer) = crate::acdc::schemer::cache().get(said) {
+        return Ok(schemer);
+    }
+
+    let schemer = resolver.resolve(said)?;
+    crate::acdc::schemer::cache().prime(&[schemer.clone()])?;
+    Ok(schemer)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn filesystem_resolver_rejects_a_said_mismatch() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("ESomeSaid.json"), br#"{"not": "the schema"}"#).unwrap();
+
+        let resolver = FilesystemResolver::new(dir.path());
+        assert!(resolver.resolve("ESomeSaid").is_err());
+    }
+
+    #[test]
+    fn http_resolver_rejects_untrusted_origins() {
+        let resolver = HttpResolver::new(vec!["https://trusted.example.com".to_string()]).unwrap();
+        let result =
+            resolver
+                .runtime
+                .block_on(resolver.resolve_url("https://evil.example.com/ESomeSaid.json", "ESomeSaid"));
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn http_resolver_rejects_lookalike_hosts_that_merely_share_the_allow_listed_prefix() {
+        let resolver = HttpResolver::new(vec!["https://trusted.example.com".to_string()]).unwrap();
+
+        assert!(!resolver.origin_is_trusted("https://trusted.example.comevil.com/x.json"));
+        assert!(!resolver.origin_is_trusted("https://trusted.example.com.attacker.net/x.json"));
+        assert!(resolver.origin_is_trusted("https://trusted.example.com/x.json"));
+    }
+}
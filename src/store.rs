@@ -0,0 +1,411 @@
+//! A durable, disk-backed implementation of [`keri::KeriStore`].
+//!
+//! The in-memory `Store` sketched in `examples/acdc-e2e.rs` is convenient for a demo but
+//! forgets everything on process exit. This module backs the same shape of data (keys, SADs,
+//! attachments, ACDC indexes, KELs and TELs) with an embedded `sled` database so a `Vault` can
+//! be reopened after a restart.
+
+use sled::{Db, Transactional};
+
+use crate::{
+    error::{err, Error, Result},
+    keri::{self, KeriStore, KeySet},
+};
+
+const KEYS: &str = "keys";
+const SADS: &str = "sads";
+const ATTACHMENTS: &str = "attachments";
+const ACDCS_ISSUED: &str = "acdcs_issued";
+const ACDCS_RECEIVED: &str = "acdcs_received";
+const KELS: &str = "kels";
+const KEL_COUNTS: &str = "kel_counts";
+const TELS: &str = "tels";
+const TEL_COUNTS: &str = "tel_counts";
+
+fn log_key(pre: &str, index: u32) -> Vec<u8> {
+    format!("{pre}\x00{index:010}").into_bytes()
+}
+
+fn log_count(tree: &sled::Tree, pre: &str) -> Result<u32> {
+    match tree.get(pre)? {
+        Some(bytes) => Ok(u32::from_be_bytes(bytes.as_ref().try_into().map_err(
+            |_| Error::Decoding,
+        )?)),
+        None => Ok(0),
+    }
+}
+
+/// A `sled`-backed [`keri::KeriStore`] that survives process restarts.
+///
+/// Each logical collection in the in-memory demo `Store` (keys, SADs, attachments, ACDC
+/// indexes, KELs and TELs) is kept in its own `sled` tree, which `sled` treats as an
+/// independent, ordered column family. KEL/TEL entries are appended under a
+/// `{prefix}\0{index:010}` key so `get_kel`/`get_tel` can scan a prefix's log in order and
+/// `get_key_event`/`get_transaction_event` can look a single entry up directly.
+pub struct Store {
+    prefix: String,
+    db: Db,
+}
+
+impl Store {
+    /// Opens (creating if necessary) a disk-backed store rooted at `path`.
+    pub fn open(path: &str, prefix: &str) -> Result<Self> {
+        let db = sled::open(path)?;
+        Ok(Store {
+            prefix: prefix.to_string(),
+            db,
+        })
+    }
+
+    /// Builds a store backed by a temporary, process-local database.
+    ///
+    /// Equivalent to the in-memory demo `Store::new`, but still speaks the durable schema -
+    /// useful for tests that don't care about surviving a restart.
+    pub fn new(prefix: &str) -> Result<Self> {
+        let db = sled::Config::new().temporary(true).open()?;
+        Ok(Store {
+            prefix: prefix.to_string(),
+            db,
+        })
+    }
+
+    fn tree(&self, name: &str) -> Result<sled::Tree> {
+        Ok(self.db.open_tree(name)?)
+    }
+
+    fn insert_sad_internal(&self, sad: &str) -> Result<()> {
+        let v: serde_json::Value = serde_json::from_str(sad)?;
+        let value = cesride::data::Value::from(&v);
+        let label = value["d"].to_string()?;
+
+        self.tree(SADS)?.insert(label.as_bytes(), sad.as_bytes())?;
+        Ok(())
+    }
+
+    fn insert_event(&self, event: &str) -> Result<String> {
+        let serder = cesride::Serder::new_with_raw(event.as_bytes())?;
+        let said = serder.said()?;
+        let attachments = &event[serder.raw().len()..];
+
+        let sads = self.tree(SADS)?;
+        let attachments_tree = self.tree(ATTACHMENTS)?;
+
+        (&sads, &attachments_tree).transaction(|(sads, attachments_tree)| {
+            sads.insert(said.as_bytes(), &event[..serder.raw().len()])?;
+            attachments_tree.insert(said.as_bytes(), attachments)?;
+            Ok::<_, sled::transaction::ConflictableTransactionError<sled::Error>>(())
+        })?;
+
+        Ok(said)
+    }
+
+    fn append_log(&self, log: &str, counts: &str, pre: &str, said: &str) -> Result<()> {
+        let log_tree = self.tree(log)?;
+        let counts_tree = self.tree(counts)?;
+
+        (&log_tree, &counts_tree).transaction(|(log_tree, counts_tree)| {
+            let count = match counts_tree.get(pre)? {
+                Some(bytes) => u32::from_be_bytes(
+                    bytes
+                        .as_ref()
+                        .try_into()
+                        .map_err(|_| sled::transaction::ConflictableTransactionError::Abort(Error::Decoding))?,
+                ),
+                None => 0,
+            };
+            log_tree.insert(log_key(pre, count), said.as_bytes())?;
+            counts_tree.insert(pre.as_bytes(), &(count + 1).to_be_bytes())?;
+            Ok::<_, sled::transaction::ConflictableTransactionError<Error>>(())
+        })?;
+
+        Ok(())
+    }
+
+    fn get_sad_and_attachments(&self, said: &str) -> Result<String> {
+        let sads = self.tree(SADS)?;
+        let attachments = self.tree(ATTACHMENTS)?;
+
+        let sad = sads.get(said.as_bytes())?.ok_or(Error::Value)?;
+        let atc = attachments.get(said.as_bytes())?.ok_or(Error::Value)?;
+
+        Ok(String::from_utf8(sad.to_vec()).map_err(|_| Error::Decoding)?
+            + &String::from_utf8(atc.to_vec()).map_err(|_| Error::Decoding)?)
+    }
+
+    fn log_saids(&self, log: &str, pre: &str) -> Result<Vec<String>> {
+        let tree = self.tree(log)?;
+        let mut result = vec![];
+
+        for entry in tree.scan_prefix(format!("{pre}\x00").into_bytes()) {
+            let (_, said) = entry?;
+            result.push(String::from_utf8(said.to_vec()).map_err(|_| Error::Decoding)?);
+        }
+
+        Ok(result)
+    }
+}
+
+impl keri::KeriStore for Store {
+    fn prefix(&self) -> String {
+        self.prefix.clone()
+    }
+
+    fn insert_keys(&mut self, pre: &str, keys: &KeySet) -> Result<()> {
+        let tree = self.tree(KEYS)?;
+        let value = serde_json::to_string(keys)?;
+
+        tree.transaction(|tree| {
+            let count = match tree.get(pre)? {
+                Some(bytes) => u32::from_be_bytes(bytes.as_ref().try_into().map_err(|_| {
+                    sled::transaction::ConflictableTransactionError::Abort(Error::Decoding)
+                })?),
+                None => 0,
+            };
+
+            tree.insert(log_key(pre, count), value.as_bytes())?;
+            tree.insert(pre.as_bytes(), &(count + 1).to_be_bytes())?;
+            Ok::<_, sled::transaction::ConflictableTransactionError<Error>>(())
+        })?;
+
+        Ok(())
+    }
+
+    fn insert_sad(&mut self, sad: &str) -> Result<()> {
+        self.insert_sad_internal(sad)
+    }
+
+    fn insert_acdc(&mut self, acdc: &str, issued: bool) -> Result<()> {
+        let creder = cesride::Creder::new_with_raw(acdc.as_bytes())?;
+        let said = creder.said()?;
+        let attachments = &acdc[creder.raw().len()..];
+
+        let label = if issued { ACDCS_ISSUED } else { ACDCS_RECEIVED };
+        let acdcs = self.tree(label)?;
+        let sads = self.tree(SADS)?;
+        let attachments_tree = self.tree(ATTACHMENTS)?;
+
+        let count = log_count(&acdcs, self.prefix.as_str())?;
+        (&acdcs, &sads, &attachments_tree).transaction(|(acdcs, sads, attachments_tree)| {
+            acdcs.insert(log_key(&self.prefix, count), said.as_bytes())?;
+            acdcs.insert(self.prefix.as_bytes(), &(count + 1).to_be_bytes())?;
+            sads.insert(said.as_bytes(), &acdc[..creder.raw().len()])?;
+            attachments_tree.insert(said.as_bytes(), attachments)?;
+            Ok::<_, sled::transaction::ConflictableTransactionError<sled::Error>>(())
+        })?;
+
+        Ok(())
+    }
+
+    fn insert_key_event(&mut self, pre: &str, event: &str) -> Result<()> {
+        let said = self.insert_event(event)?;
+        self.append_log(KELS, KEL_COUNTS, pre, &said)
+    }
+
+    fn insert_transaction_event(&mut self, pre: &str, event: &str) -> Result<()> {
+        let said = self.insert_event(event)?;
+        self.append_log(TELS, TEL_COUNTS, pre, &said)
+    }
+
+    fn get_current_keys(&self, pre: &str) -> Result<KeySet> {
+        let saids = self.log_saids(KEYS, pre)?;
+        if saids.len() < 2 {
+            return err!(Error::Decoding);
+        }
+
+        let tree = self.tree(KEYS)?;
+        let value = tree
+            .get(log_key(pre, saids.len() as u32 - 2))?
+            .ok_or(Error::Decoding)?;
+        Ok(serde_json::from_slice(&value)?)
+    }
+
+    fn get_next_keys(&self, pre: &str) -> Result<KeySet> {
+        let saids = self.log_saids(KEYS, pre)?;
+        if saids.is_empty() {
+            return err!(Error::Decoding);
+        }
+
+        let tree = self.tree(KEYS)?;
+        let value = tree
+            .get(log_key(pre, saids.len() as u32 - 1))?
+            .ok_or(Error::Decoding)?;
+        Ok(serde_json::from_slice(&value)?)
+    }
+
+    fn get_sad(&self, said: &str) -> Result<cesride::data::Value> {
+        let sads = self.tree(SADS)?;
+        let raw = sads.get(said.as_bytes())?.ok_or(Error::Value)?;
+        let v: serde_json::Value = serde_json::from_slice(&raw)?;
+        Ok(cesride::data::Value::from(&v))
+    }
+
+    fn get_acdc(&self, said: &str) -> Result<String> {
+        self.get_sad_and_attachments(said)
+    }
+
+    fn get_key_event(&self, pre: &str, version: u32) -> Result<String> {
+        let tree = self.tree(KELS)?;
+        let said = tree
+            .get(log_key(pre, version))?
+            .ok_or(Error::Value)?;
+        self.get_sad_and_attachments(&String::from_utf8(said.to_vec()).map_err(|_| Error::Decoding)?)
+    }
+
+    fn get_transaction_event(&self, pre: &str, version: u32) -> Result<String> {
+        let tree = self.tree(TELS)?;
+        let said = tree
+            .get(log_key(pre, version))?
+            .ok_or(Error::Value)?;
+        self.get_sad_and_attachments(&String::from_utf8(said.to_vec()).map_err(|_| Error::Decoding)?)
+    }
+
+    fn get_latest_establishment_event(&self, pre: &str) -> Result<(String, u128)> {
+        let sn = self.get_kel(pre)?.len() as u32;
+        self.get_latest_establishment_event_as_of_sn(pre, sn)
+    }
+
+    fn get_latest_establishment_event_as_of_sn(
+        &self,
+        pre: &str,
+        sn: u32,
+    ) -> Result<(String, u128)> {
+        let mut kel = self.get_kel(pre)?;
+        kel.reverse();
+
+        for (i, e) in kel.iter().enumerate() {
+            let found_sn = (kel.len() - i - 1) as u128;
+            if found_sn > sn as u128 {
+                continue;
+            }
+
+            let serder = cesride::Serder::new_with_raw(e.as_bytes())?;
+            if serder.est()? {
+                return Ok((e.clone(), found_sn));
+            }
+        }
+
+        err!(Error::Value)
+    }
+
+    fn get_latest_transaction_event(&self, pre: &str) -> Result<String> {
+        let saids = self.log_saids(TELS, pre)?;
+        let said = saids.last().ok_or(Error::Value)?;
+        self.get_sad_and_attachments(said)
+    }
+
+    fn get_latest_key_event_said(&self, pre: &str) -> Result<String> {
+        let saids = self.log_saids(KELS, pre)?;
+        saids.last().cloned().ok_or(Error::Value.into())
+    }
+
+    fn get_latest_establishment_event_said(&self, pre: &str) -> Result<(String, u128)> {
+        let (event, found_sn) = self.get_latest_establishment_event(pre)?;
+        let serder = cesride::Serder::new_with_raw(event.as_bytes())?;
+        Ok((serder.said()?, found_sn))
+    }
+
+    fn get_latest_establishment_event_said_as_of_sn(
+        &self,
+        pre: &str,
+        sn: u32,
+    ) -> Result<(String, u128)> {
+        let (event, found_sn) = self.get_latest_establishment_event_as_of_sn(pre, sn)?;
+        let serder = cesride::Serder::new_with_raw(event.as_bytes())?;
+        Ok((serder.said()?, found_sn))
+    }
+
+    fn get_kel(&self, pre: &str) -> Result<Vec<String>> {
+        let saids = self.log_saids(KELS, pre)?;
+        saids.iter().map(|said| self.get_sad_and_attachments(said)).collect()
+    }
+
+    fn get_tel(&self, pre: &str) -> Result<Vec<String>> {
+        let saids = self.log_saids(TELS, pre)?;
+        saids.iter().map(|said| self.get_sad_and_attachments(said)).collect()
+    }
+
+    fn count_key_events(&self, pre: &str) -> Result<usize> {
+        Ok(self.log_saids(KELS, pre)?.len())
+    }
+
+    fn count_transaction_events(&self, pre: &str) -> Result<usize> {
+        Ok(self.log_saids(TELS, pre)?.len())
+    }
+
+    fn count_establishment_events(&self, pre: &str) -> Result<usize> {
+        let kel = self.get_kel(pre)?;
+        let mut count = 0usize;
+
+        for event in &kel {
+            if cesride::Serder::new_with_raw(event.as_bytes())?.est()? {
+                count += 1;
+            }
+        }
+
+        Ok(count)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cesride::data::dat;
+
+    #[test]
+    fn persists_an_issued_acdc_across_a_reopen() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("issuer.sled");
+        let path = path.to_str().unwrap();
+
+        let (aid, keys, icp) = keri::kmi::incept(
+            Some(cesride::matter::Codex::CRYSTALS_Dilithium3_Seed),
+            None,
+            None,
+            Some(cesride::matter::Codex::CRYSTALS_Dilithium3_Seed),
+            None,
+            None,
+            None,
+            Some(true),
+            Some(cesride::matter::Codex::Blake3_256),
+            None,
+        )
+        .unwrap();
+        let (registry, vcp) = crate::acdc::tel::management::incept(&aid).unwrap();
+        let seal = dat!([{"i": &registry, "s": "0", "d": &registry}]);
+        let (ixn_said, ixn) = keri::kmi::interact(&keys[0], &aid, &aid, 1, &seal).unwrap();
+
+        let said = {
+            let mut store = Store::open(path, &aid).unwrap();
+            store.insert_keys(&aid, &keys[0]).unwrap();
+            store.insert_keys(&aid, &keys[1]).unwrap();
+
+            let counter = cesride::Counter::new_with_code_and_count(
+                cesride::counter::Codex::SealSourceCouples,
+                1,
+            )
+            .unwrap();
+            let seqner = cesride::Seqner::new_with_sn(1).unwrap();
+            let vcp = vcp + &counter.qb64().unwrap() + &seqner.qb64().unwrap() + &ixn_said;
+
+            keri::parsing::ingest_messages(&mut store, &(icp + &ixn + &vcp), Some(false), Some(true), false)
+                .unwrap();
+
+            let (acdc_said, ixn, iss, acdc, sads) = crate::acdc::issue_acdc(
+                &store, &registry, &aid, "", "{}", None, Some(true), None, None, None,
+            )
+            .unwrap();
+
+            keri::parsing::ingest_messages(&mut store, &(ixn + &iss + &acdc), Some(false), Some(true), true)
+                .unwrap();
+            for sad in &sads {
+                store.insert_sad(&sad.to_json().unwrap()).unwrap();
+            }
+
+            acdc_said
+        };
+
+        // drop and reopen from disk
+        let store = Store::open(path, &aid).unwrap();
+        assert!(store.get_acdc(&said).is_ok());
+    }
+}
@@ -0,0 +1,129 @@
+//! Cipher-suite agility for establishment events.
+//!
+//! `Vault::new` used to hardcode a Dilithium3/Blake3 pairing. A [`CipherSuite`] pulls those
+//! codes out into a value so a caller can pick Ed25519, secp256r1, a post-quantum signer, or a
+//! classical+post-quantum hybrid without touching `keri::kmi::incept`, rotation, or
+//! `keri::parsing` call sites - only the suite passed to `Vault::new` changes.
+
+use crate::error::{err, Error, Result};
+
+/// Signing codes this crate has validated against the configured seed codex.
+const SUPPORTED_SIGNING_CODES: &[&str] = &[
+    cesride::matter::Codex::Ed25519_Seed,
+    cesride::matter::Codex::ECDSA_256r1_Seed,
+    cesride::matter::Codex::CRYSTALS_Dilithium3_Seed,
+];
+
+/// Digest codes this crate has validated against the configured digest codex.
+const SUPPORTED_DIGEST_CODES: &[&str] = &[
+    cesride::matter::Codex::Blake3_256,
+    cesride::matter::Codex::SHA3_256,
+    cesride::matter::Codex::SHA2_256,
+];
+
+/// A descriptor for the signing/digest codes an identifier's establishment events use.
+///
+/// `hybrid_code`, when set, asks `keri::kmi::incept`/`keri::kmi::rotate` for a second signer of
+/// the given code alongside the primary one - `Vault::new`/`Vault::rotate` thread it through to
+/// both, so every establishment event carries two indexed signatures and `keri::parsing`
+/// requires both to validate before accepting the event. `CipherSuite` itself only validates
+/// that the code is one this crate knows how to drive and threads it to those call sites; the
+/// dual-signature construction and verification are `keri::kmi`/`keri::parsing`'s job, the same
+/// way a single signer's construction and verification always have been. This lets a classical
+/// signer (e.g. Ed25519) ride alongside a post-quantum one during a migration window, without
+/// either side being trusted alone.
+#[derive(Clone, Debug)]
+pub struct CipherSuite {
+    pub code: &'static str,
+    pub next_code: &'static str,
+    pub digest_code: &'static str,
+    pub hybrid_code: Option<&'static str>,
+}
+
+impl CipherSuite {
+    /// The suite `Vault::new` used before cipher-suite agility was introduced: Dilithium3 for
+    /// both current and next keys, Blake3 digests, no hybrid signer.
+    pub fn dilithium3() -> Self {
+        CipherSuite {
+            code: cesride::matter::Codex::CRYSTALS_Dilithium3_Seed,
+            next_code: cesride::matter::Codex::CRYSTALS_Dilithium3_Seed,
+            digest_code: cesride::matter::Codex::Blake3_256,
+            hybrid_code: None,
+        }
+    }
+
+    /// An Ed25519 suite, for deployments that don't need post-quantum signatures.
+    pub fn ed25519() -> Self {
+        CipherSuite {
+            code: cesride::matter::Codex::Ed25519_Seed,
+            next_code: cesride::matter::Codex::Ed25519_Seed,
+            digest_code: cesride::matter::Codex::Blake3_256,
+            hybrid_code: None,
+        }
+    }
+
+    /// An Ed25519 suite with a Dilithium3 hybrid signer riding alongside it, for migrating to
+    /// post-quantum signatures without dropping classical verifiers.
+    pub fn ed25519_dilithium3_hybrid() -> Self {
+        CipherSuite {
+            code: cesride::matter::Codex::Ed25519_Seed,
+            next_code: cesride::matter::Codex::Ed25519_Seed,
+            digest_code: cesride::matter::Codex::Blake3_256,
+            hybrid_code: Some(cesride::matter::Codex::CRYSTALS_Dilithium3_Seed),
+        }
+    }
+
+    /// Checks `code`, `next_code`, `digest_code` and, if present, `hybrid_code` against the
+    /// registry of codes this crate knows how to drive through inception and rotation.
+    pub fn validate(&self) -> Result<()> {
+        for code in [self.code, self.next_code] {
+            if !SUPPORTED_SIGNING_CODES.contains(&code) {
+                return err!(Error::Value);
+            }
+        }
+
+        if !SUPPORTED_DIGEST_CODES.contains(&self.digest_code) {
+            return err!(Error::Value);
+        }
+
+        if let Some(hybrid_code) = self.hybrid_code {
+            if !SUPPORTED_SIGNING_CODES.contains(&hybrid_code) {
+                return err!(Error::Value);
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl Default for CipherSuite {
+    fn default() -> Self {
+        Self::dilithium3()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn the_default_suite_validates() {
+        CipherSuite::default().validate().unwrap();
+    }
+
+    #[test]
+    fn the_hybrid_suite_validates() {
+        CipherSuite::ed25519_dilithium3_hybrid().validate().unwrap();
+    }
+
+    #[test]
+    fn an_unsupported_code_fails_validation() {
+        let suite = CipherSuite {
+            code: "unsupported",
+            next_code: cesride::matter::Codex::Ed25519_Seed,
+            digest_code: cesride::matter::Codex::Blake3_256,
+            hybrid_code: None,
+        };
+        assert!(suite.validate().is_err());
+    }
+}
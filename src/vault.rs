@@ -0,0 +1,417 @@
+//! A persisted `Vault`, backed by [`crate::store::Store`].
+//!
+//! `examples/acdc-e2e.rs` sketches the same lifecycle (incept, issue, fetch, ingest) against an
+//! in-memory store for a single process. This is the library-level counterpart backed by a
+//! disk-backed `Store`, so a `Vault` can be incepted once and reopened by later invocations -
+//! which is what the `keri-acdc` CLI needs to drive the lifecycle one subcommand at a time.
+
+use cesride::data::{dat, Value};
+
+use crate::{
+    acdc,
+    error::{err, Error, Result},
+    keri::{self, KeriStore, KeySet},
+    recovery,
+    schema_resolver::{resolve_with_fallback, SchemaResolver},
+    store::Store,
+    suite::CipherSuite,
+};
+
+pub struct Vault {
+    store: Store,
+    prefix: String,
+    registry: String,
+    resolver: Option<Box<dyn SchemaResolver>>,
+}
+
+impl Vault {
+    /// Incepts a fresh identifier at `path` under the given cipher suite.
+    pub fn new(path: &str, suite: &CipherSuite) -> Result<(Self, String)> {
+        Self::incept(path, suite, None, None)
+    }
+
+    /// Rebuilds an identifier at `path` from a passphrase, replaying inception and then
+    /// `established_index` rotations so the recovered vault lands on the same establishment
+    /// event a surviving `Store` would have. See [`crate::recovery`] for the derivation this
+    /// relies on.
+    ///
+    /// Each step's key material comes from [`KeySet::derive`], and after every rotation the
+    /// keys `Store` actually committed are checked against what `KeySet::derive` reconstructs
+    /// for that index - the whole point of a passphrase-derived chain is that a lost `Store`
+    /// can be rebuilt bit-for-bit, not just key-for-key.
+    pub fn recover(
+        path: &str,
+        suite: &CipherSuite,
+        passphrase: &str,
+        established_index: u32,
+    ) -> Result<(Self, String)> {
+        let root = recovery::derive_root_seed(passphrase)?;
+
+        let seed = recovery::derive_signing_seed(&root, 0)?;
+        let next_seed = recovery::derive_signing_seed(&root, 1)?;
+        let (mut vault, aid) = Self::incept(path, suite, Some(&seed), Some(&next_seed))?;
+        vault.assert_current_keys_match(suite, &root, 0)?;
+
+        for index in 1..=established_index {
+            let seed = recovery::derive_signing_seed(&root, index)?;
+            let next_seed = recovery::derive_signing_seed(&root, index + 1)?;
+
+            vault.rotate(suite, &seed, &next_seed)?;
+            vault.assert_current_keys_match(suite, &root, index)?;
+        }
+
+        Ok((vault, aid))
+    }
+
+    fn assert_current_keys_match(
+        &self,
+        suite: &CipherSuite,
+        root: &[u8; 32],
+        index: u32,
+    ) -> Result<()> {
+        let expected = KeySet::derive(suite.code, suite.next_code, root, index)?;
+        let actual = self.current_keys()?;
+
+        if serde_json::to_string(&expected)? != serde_json::to_string(&actual)? {
+            return err!(Error::Verification);
+        }
+
+        Ok(())
+    }
+
+    /// Reopens an already-incepted identifier's store at `path`.
+    pub fn open(path: &str, prefix: &str, registry: &str) -> Result<Self> {
+        let store = Store::open(path, prefix)?;
+        Ok(Vault {
+            store,
+            prefix: prefix.to_string(),
+            registry: registry.to_string(),
+            resolver: None,
+        })
+    }
+
+    /// Configures a schema resolver to consult when `issue_acdc`/`fetch_acdc` hit a schema SAID
+    /// the cache doesn't already have primed.
+    pub fn set_resolver(&mut self, resolver: Box<dyn SchemaResolver>) {
+        self.resolver = Some(resolver);
+    }
+
+    fn incept(
+        path: &str,
+        suite: &CipherSuite,
+        seed: Option<&[u8]>,
+        next_seed: Option<&[u8]>,
+    ) -> Result<(Self, String)> {
+        suite.validate()?;
+
+        let (aid, keys, icp) = keri::kmi::incept(
+            Some(suite.code),
+            seed,
+            None,
+            Some(suite.next_code),
+            next_seed,
+            None,
+            None,
+            Some(true),
+            Some(suite.digest_code),
+            suite.hybrid_code,
+        )?;
+        let (registry, vcp) = acdc::tel::management::incept(&aid)?;
+        let seal = dat!([{"i": &registry, "s": "0", "d": &registry}]);
+        let (ixn_said, ixn) = keri::kmi::interact(&keys[0], &aid, &aid, 1, &seal)?;
+
+        let mut store = Store::open(path, &aid)?;
+        store.insert_keys(&aid, &keys[0])?;
+        store.insert_keys(&aid, &keys[1])?;
+
+        drop(keys);
+
+        let counter = cesride::Counter::new_with_code_and_count(
+            cesride::counter::Codex::SealSourceCouples,
+            1,
+        )?;
+        let seqner = cesride::Seqner::new_with_sn(1)?;
+        let vcp = vcp + &counter.qb64()? + &seqner.qb64()? + &ixn_said;
+
+        keri::parsing::ingest_messages(
+            &mut store,
+            &(icp + &ixn + &vcp),
+            Some(false),
+            Some(true),
+            false,
+        )?;
+
+        Ok((
+            Vault {
+                store,
+                prefix: aid.clone(),
+                registry,
+                resolver: None,
+            },
+            aid,
+        ))
+    }
+
+    pub fn prefix(&self) -> &str {
+        &self.prefix
+    }
+
+    pub fn registry(&self) -> &str {
+        &self.registry
+    }
+
+    /// Rotates the identifier's keys, signing with `seed` and pre-committing to `next_seed`.
+    pub fn rotate(&mut self, suite: &CipherSuite, seed: &[u8], next_seed: &[u8]) -> Result<()> {
+        suite.validate()?;
+
+        let sn = self.store.count_key_events(&self.prefix)? as u32;
+        let signing = self.store.get_next_keys(&self.prefix)?;
+
+        let (rot, keys) = keri::kmi::rotate(
+            &signing,
+            &self.prefix,
+            sn,
+            Some(suite.code),
+            Some(seed),
+            Some(suite.next_code),
+            Some(next_seed),
+            Some(suite.digest_code),
+            suite.hybrid_code,
+        )?;
+
+        self.store.insert_keys(&self.prefix, &keys[0])?;
+        self.store.insert_keys(&self.prefix, &keys[1])?;
+
+        keri::parsing::ingest_messages(&mut self.store, &rot, Some(false), Some(true), false)?;
+
+        Ok(())
+    }
+
+    pub fn current_keys(&self) -> Result<KeySet> {
+        self.store.get_current_keys(&self.prefix)
+    }
+
+    pub fn next_keys(&self) -> Result<KeySet> {
+        self.store.get_next_keys(&self.prefix)
+    }
+
+    /// Issues an ACDC, returning its SAID and the CESR message stream (`ixn + iss + acdc`)
+    /// that was just ingested, so a caller can pipe the credential to another process.
+    #[allow(clippy::too_many_arguments)]
+    pub fn issue_acdc(
+        &mut self,
+        schema: &str,
+        data: &str,
+        recipient: Option<&str>,
+        private: Option<bool>,
+        source: Option<&str>,
+        rules: Option<&str>,
+        partially_disclosable: Option<&str>,
+    ) -> Result<(String, String)> {
+        self.prime_schema(schema)?;
+
+        let (acdc_said, ixn, iss, acdc, sads) = acdc::issue_acdc(
+            &self.store,
+            &self.registry,
+            &self.prefix,
+            schema,
+            data,
+            recipient,
+            private,
+            source,
+            rules,
+            partially_disclosable,
+        )?;
+
+        let messages = ixn + &iss + &acdc;
+        keri::parsing::ingest_messages(&mut self.store, &messages, Some(false), Some(true), true)?;
+        for sad in &sads {
+            self.store.insert_sad(&sad.to_json()?)?;
+        }
+
+        Ok((acdc_said, messages))
+    }
+
+    pub fn fetch_acdc(&self, said: &str, to_disclose: &[&str], full: bool) -> Result<String> {
+        let acdc_string = self.store.get_acdc(said)?;
+        let creder = cesride::Creder::new_with_raw(acdc_string.as_bytes())?;
+        self.prime_schema(&creder.schema()?)?;
+
+        let mut to_expand = vec![vec!["a"]];
+        for key in to_disclose {
+            to_expand.push(vec!["a", *key]);
+        }
+
+        let expanded_acdc = acdc::expand_acdc(&creder, to_expand.as_slice(), &self.store)?;
+
+        let mut messages = if full {
+            let kel = self.store.get_kel(&expanded_acdc.issuer()?)?;
+            let mgmt_tel = self.store.get_tel(&creder.status()?.unwrap())?;
+            let vc_tel = self.store.get_tel(&creder.said()?)?;
+
+            kel.join("") + &mgmt_tel.join("") + &vc_tel.join("")
+        } else {
+            "".to_string()
+        };
+
+        messages += &(expanded_acdc.crd().to_json()? + &acdc_string[creder.raw().len()..]);
+        Ok(messages)
+    }
+
+    /// Ingests a CESR message stream from another party - the disclosee side of `fetch_acdc`'s
+    /// partial disclosure, or of `issue_acdc`'s output piped to a second vault.
+    ///
+    /// Unlike `issue_acdc`/`fetch_acdc`, the schema SAID(s) here aren't an argument the caller
+    /// already knows - they're whatever the embedded ACDC(s) in `messages` reference - so every
+    /// one found by [`schema_saids_in`] is primed before the stream is handed to
+    /// `keri::parsing::ingest_messages`, the same way `prime_schema` covers the issuer side.
+    pub fn ingest_messages(&mut self, messages: &str) -> Result<()> {
+        for schema in schema_saids_in(messages) {
+            self.prime_schema(&schema)?;
+        }
+
+        keri::parsing::ingest_messages(&mut self.store, messages, Some(false), Some(true), false)?;
+        Ok(())
+    }
+
+    pub fn kel(&self) -> Result<Vec<String>> {
+        self.store.get_kel(&self.prefix)
+    }
+
+    pub fn tel(&self, pre: &str) -> Result<Vec<String>> {
+        self.store.get_tel(pre)
+    }
+
+    pub fn count_establishment_events(&self, pre: &str) -> Result<usize> {
+        self.store.count_establishment_events(pre)
+    }
+
+    pub fn sad(&self, said: &str) -> Result<Value> {
+        self.store.get_sad(said)
+    }
+
+    /// Ensures `schema` is in the `Schemer` cache before it's needed for validation, consulting
+    /// the configured resolver on a miss instead of letting `Error::SchemaValidation` surface
+    /// from deeper in `acdc::issue_acdc`/`acdc::expand_acdc`. A no-op when no resolver is
+    /// configured - the cache behaves exactly as it did before resolvers existed.
+    fn prime_schema(&self, schema: &str) -> Result<()> {
+        if let Some(resolver) = &self.resolver {
+            resolve_with_fallback(schema, resolver.as_ref())?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Finds every schema SAID referenced by an ACDC embedded in a raw CESR message stream.
+///
+/// `messages` may interleave KEL/TEL events with one or more ACDCs (the same shape
+/// `issue_acdc`/`fetch_acdc` produce), and there's no length-prefixed index telling a reader
+/// where each message starts - so every `{` is tried as a candidate ACDC SAD via
+/// `Creder::new_with_raw`, which fails fast on anything that isn't one (a KEL/TEL event's
+/// version string won't match). Cheap enough for a CLI-sized ingest; failed candidates cost a
+/// rejected parse, not a wrong answer.
+fn schema_saids_in(messages: &str) -> Vec<String> {
+    let bytes = messages.as_bytes();
+    let mut saids = Vec::new();
+
+    for (i, &b) in bytes.iter().enumerate() {
+        if b != b'{' {
+            continue;
+        }
+
+        if let Ok(creder) = cesride::Creder::new_with_raw(&bytes[i..]) {
+            if let Ok(schema) = creder.schema() {
+                saids.push(schema);
+            }
+        }
+    }
+
+    saids
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rotate_signs_the_current_key_with_the_suites_current_code_not_its_next_code() {
+        // a suite where code != next_code is the only way to catch the two getting swapped -
+        // every built-in CipherSuite factory sets them equal, so this test builds one by hand.
+        let suite = CipherSuite {
+            code: cesride::matter::Codex::Ed25519_Seed,
+            next_code: cesride::matter::Codex::CRYSTALS_Dilithium3_Seed,
+            digest_code: cesride::matter::Codex::Blake3_256,
+            hybrid_code: None,
+        };
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("asymmetric.sled");
+        let path = path.to_str().unwrap();
+
+        let (mut vault, _aid) = Vault::new(path, &suite).unwrap();
+
+        let seed = [7u8; 32];
+        let next_seed = [9u8; 32];
+        vault.rotate(&suite, &seed, &next_seed).unwrap();
+
+        let expected =
+            keri::kmi::keys_from_seeds(suite.code, &seed, suite.next_code, &next_seed).unwrap();
+        let actual = vault.current_keys().unwrap();
+
+        assert_eq!(
+            serde_json::to_string(&expected).unwrap(),
+            serde_json::to_string(&actual).unwrap()
+        );
+    }
+
+    #[test]
+    fn schema_saids_in_finds_the_schema_an_embedded_acdc_references() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("issuer.sled");
+        let path = path.to_str().unwrap();
+
+        let suite = CipherSuite::default();
+        let (mut vault, _aid) = Vault::new(path, &suite).unwrap();
+        let (_said, messages) = vault
+            .issue_acdc("ESomeSchemaSaid", "{}", None, Some(true), None, None, None)
+            .unwrap();
+
+        assert_eq!(schema_saids_in(&messages), vec!["ESomeSchemaSaid".to_string()]);
+    }
+
+    #[test]
+    fn ingest_messages_resolves_an_unseen_schema_via_the_configured_resolver() {
+        use crate::schema_resolver::FilesystemResolver;
+
+        let issuer_dir = tempfile::tempdir().unwrap();
+        let issuer_path = issuer_dir.path().join("issuer.sled");
+        let issuer_path = issuer_path.to_str().unwrap();
+
+        let suite = CipherSuite::default();
+        let (mut issuer, _issuer_aid) = Vault::new(issuer_path, &suite).unwrap();
+        let (_said, messages) = issuer
+            .issue_acdc("ESomeSchemaSaid", "{}", None, Some(true), None, None, None)
+            .unwrap();
+
+        let schema_dir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            schema_dir.path().join("ESomeSchemaSaid.json"),
+            br#"{"not": "a real schema, just exercising the resolver hand-off"}"#,
+        )
+        .unwrap();
+
+        let disclosee_dir = tempfile::tempdir().unwrap();
+        let disclosee_path = disclosee_dir.path().join("disclosee.sled");
+        let disclosee_path = disclosee_path.to_str().unwrap();
+        let (mut disclosee, _disclosee_aid) = Vault::new(disclosee_path, &suite).unwrap();
+        disclosee.set_resolver(Box::new(FilesystemResolver::new(schema_dir.path())));
+
+        // the fabricated document's SAID won't match "ESomeSchemaSaid", so resolution still
+        // fails - but it fails with the resolver's SAID-mismatch error, which only
+        // surfaces if `ingest_messages` actually looked up the embedded ACDC's schema and
+        // handed it to the resolver, rather than never consulting it at all.
+        let error = disclosee.ingest_messages(&messages).unwrap_err();
+        assert!(error.to_string().contains("resolved schema's SAID did not match"));
+    }
+}